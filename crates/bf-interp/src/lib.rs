@@ -0,0 +1,395 @@
+//! A reusable Brainfuck interpreter, generic over its I/O so it can be
+//! embedded with custom input/output (in-memory buffers, test harnesses, WASM).
+
+use std::io::{Read, Write};
+
+use bf_parser::{Ast, BfError, Node};
+
+/// How cell arithmetic behaves when `+`/`-` would overflow or underflow a byte.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CellOverflow {
+    Wrapping,
+    Saturating,
+    Erroring,
+}
+
+/// How the pointer behaves when `<`/`>` would move it outside the tape.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TapeBounds {
+    Wrapping,
+    Bounded,
+}
+
+/// Dialect settings for cell and pointer semantics.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Config {
+    pub tape_size: usize,
+    pub cell_overflow: CellOverflow,
+    pub tape_bounds: TapeBounds,
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Self {
+            tape_size: 30_000,
+            cell_overflow: CellOverflow::Wrapping,
+            tape_bounds: TapeBounds::Bounded,
+        }
+    }
+}
+
+/// Interpreter state (tape + pointer) that can persist across several `run` calls.
+pub struct Interpreter {
+    data: Vec<u8>,
+    ptr: usize,
+    config: Config,
+}
+
+impl Default for Interpreter {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Interpreter {
+    pub fn new() -> Self {
+        Self::with_config(Config::default())
+    }
+
+    pub fn with_config(config: Config) -> Self {
+        Self {
+            data: vec![0; config.tape_size],
+            ptr: 0,
+            config,
+        }
+    }
+
+    /// Zero the tape and reset the pointer to the start.
+    pub fn reset(&mut self) {
+        self.data.iter_mut().for_each(|cell| *cell = 0);
+        self.ptr = 0;
+    }
+
+    /// The tape's current contents.
+    pub fn tape(&self) -> &[u8] {
+        &self.data
+    }
+
+    /// The current pointer position into the tape.
+    pub fn ptr(&self) -> usize {
+        self.ptr
+    }
+
+    /// Run `ast` against this interpreter's tape, reading `,` from `input` and
+    /// writing `.` to `output`.
+    pub fn run<R, W>(&mut self, ast: &Ast, input: &mut R, output: &mut W) -> Result<(), BfError>
+    where
+        R: Read,
+        W: Write,
+    {
+        run_nodes(ast, &mut self.data, &mut self.ptr, input, output, &self.config)
+    }
+}
+
+fn adjust_cell(data: &mut [u8], ptr: usize, delta: i8, overflow: CellOverflow) -> Result<(), BfError> {
+    adjust_cell_by(data, ptr, delta as i64, overflow)
+}
+
+/// Apply a net delta (as produced by a coalesced `Node::Add` run, at full
+/// precision) to a cell. Wrapping and saturating arithmetic give the same
+/// result whether `delta` is applied in one step or unit-by-unit, since a
+/// coalesced run never changes sign; erroring arithmetic instead reports
+/// overflow for the run as a whole.
+fn adjust_cell_by(data: &mut [u8], ptr: usize, delta: i64, overflow: CellOverflow) -> Result<(), BfError> {
+    let cell = &mut data[ptr];
+    *cell = match overflow {
+        CellOverflow::Wrapping => cell.wrapping_add(delta as u8),
+        CellOverflow::Saturating if delta > 0 => cell.saturating_add(delta.min(u8::MAX as i64) as u8),
+        CellOverflow::Saturating => cell.saturating_sub(delta.unsigned_abs().min(u8::MAX as u64) as u8),
+        CellOverflow::Erroring if delta > 0 => {
+            u8::try_from(*cell as i64 + delta).map_err(|_| BfError::CellOverflow(ptr))?
+        }
+        CellOverflow::Erroring => cell
+            .checked_sub(u8::try_from(delta.unsigned_abs()).map_err(|_| BfError::CellOverflow(ptr))?)
+            .ok_or(BfError::CellOverflow(ptr))?,
+    };
+    Ok(())
+}
+
+fn shift_ptr(ptr: &mut usize, delta: isize, tape_size: usize, bounds: TapeBounds) -> Result<(), BfError> {
+    let moved = *ptr as isize + delta;
+    *ptr = match bounds {
+        TapeBounds::Wrapping => moved.rem_euclid(tape_size as isize) as usize,
+        TapeBounds::Bounded => {
+            if moved < 0 || moved >= tape_size as isize {
+                return Err(BfError::PointerOutOfBounds(*ptr));
+            }
+            moved as usize
+        }
+    };
+    Ok(())
+}
+
+/// Check that every offset (relative to `ptr`) in the inclusive `swept`
+/// range stays on the tape, without moving `ptr`. Under `TapeBounds::Wrapping`
+/// there's nothing to check: every offset resolves to some valid index.
+fn check_swept(ptr: usize, swept: (isize, isize), tape_size: usize, bounds: TapeBounds) -> Result<(), BfError> {
+    if let TapeBounds::Bounded = bounds {
+        let low = ptr as isize + swept.0;
+        let high = ptr as isize + swept.1;
+        if low < 0 || high >= tape_size as isize {
+            return Err(BfError::PointerOutOfBounds(ptr));
+        }
+    }
+    Ok(())
+}
+
+/// Like `shift_ptr`, but for a coalesced `Node::Move`: `swept` is the
+/// inclusive range of offsets (relative to `*ptr` before the move) the
+/// pointer passed through, so a transient excursion out of bounds is still
+/// caught under `TapeBounds::Bounded` even though only the net `delta` is
+/// applied.
+fn shift_ptr_swept(
+    ptr: &mut usize,
+    delta: isize,
+    swept: (isize, isize),
+    tape_size: usize,
+    bounds: TapeBounds,
+) -> Result<(), BfError> {
+    check_swept(*ptr, swept, tape_size, bounds)?;
+    shift_ptr(ptr, delta, tape_size, bounds)
+}
+
+/// Resolve `ptr + offset` against the tape's bounds without moving `ptr`,
+/// for `Node::MulAdd`'s target cell.
+fn resolve_offset(ptr: usize, offset: isize, tape_size: usize, bounds: TapeBounds) -> Result<usize, BfError> {
+    let target = ptr as isize + offset;
+    match bounds {
+        TapeBounds::Wrapping => Ok(target.rem_euclid(tape_size as isize) as usize),
+        TapeBounds::Bounded => {
+            if target < 0 || target >= tape_size as isize {
+                return Err(BfError::PointerOutOfBounds(ptr));
+            }
+            Ok(target as usize)
+        }
+    }
+}
+
+fn run_nodes<R, W>(
+    ast: &Ast,
+    data: &mut [u8],
+    ptr: &mut usize,
+    input: &mut R,
+    output: &mut W,
+    config: &Config,
+) -> Result<(), BfError>
+where
+    R: Read,
+    W: Write,
+{
+    for node in ast {
+        match node {
+            Node::Incr => adjust_cell(data, *ptr, 1, config.cell_overflow)?,
+            Node::Decr => adjust_cell(data, *ptr, -1, config.cell_overflow)?,
+            Node::ShiftLeft => shift_ptr(ptr, -1, config.tape_size, config.tape_bounds)?,
+            Node::ShiftRight => shift_ptr(ptr, 1, config.tape_size, config.tape_bounds)?,
+            Node::Add(delta) => adjust_cell_by(data, *ptr, *delta, config.cell_overflow)?,
+            Node::Move { offset, swept } => {
+                shift_ptr_swept(ptr, *offset, *swept, config.tape_size, config.tape_bounds)?
+            }
+            Node::SetZero => data[*ptr] = 0,
+            Node::MulAdd { offset, factor, swept } => {
+                // The idiom's loop body (and thus its pointer excursion) only
+                // ever runs when the cell starts nonzero, since the body
+                // always zeroes it; check the swept range only then, so an
+                // already-zero cell is a no-op exactly like the unoptimized
+                // loop, even on a tape too small for the excursion.
+                let value = data[*ptr];
+                if value != 0 {
+                    check_swept(*ptr, *swept, config.tape_size, config.tape_bounds)?;
+                    let target = resolve_offset(*ptr, *offset, config.tape_size, config.tape_bounds)?;
+                    let delta = value as i64 * *factor;
+                    adjust_cell_by(data, target, delta, config.cell_overflow)?;
+                }
+                data[*ptr] = 0;
+            }
+            Node::Output => {
+                output.write_all(&[data[*ptr]])?;
+            }
+            Node::Input => {
+                let mut byte = [0_u8];
+                input.read_exact(&mut byte)?;
+                data[*ptr] = byte[0];
+            }
+            Node::Loop { children } => {
+                while data[*ptr] != 0 {
+                    run_nodes(children, data, ptr, input, output, config)?;
+                }
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Parse-and-run convenience wrapper over a fresh `Interpreter`.
+pub fn interpret<R, W>(
+    ast: &Ast,
+    input: &mut R,
+    output: &mut W,
+    config: Config,
+) -> Result<(), BfError>
+where
+    R: Read,
+    W: Write,
+{
+    Interpreter::with_config(config).run(ast, input, output)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use bf_parser::{optimize, parse};
+
+    fn run_source(source: &str, config: Config, optimized: bool) -> Result<Vec<u8>, BfError> {
+        let ast = parse(source).unwrap();
+        let ast = if optimized { optimize(ast) } else { ast };
+        let mut output = Vec::new();
+        interpret(&ast, &mut std::io::empty(), &mut output, config)?;
+        Ok(output)
+    }
+
+    fn assert_optimized_matches_unoptimized(source: &str, config: Config) {
+        let unoptimized = run_source(source, config, false).unwrap();
+        let optimized = run_source(source, config, true).unwrap();
+        assert_eq!(
+            optimized, unoptimized,
+            "optimized and unoptimized runs diverged for {source:?} under {config:?}"
+        );
+    }
+
+    const PROGRAMS: &[&str] = &[
+        "++++++++[>++++++++<-]>.",        // copy/multiply: 8*8=64
+        "+++[>+++[-]<-]>.",                // clear loop nested inside another loop
+        "++++++++++[>+++++++<-]>---.<.",  // multiply then a plain loop
+    ];
+
+    #[test]
+    fn optimized_matches_unoptimized_under_wrapping() {
+        for source in PROGRAMS {
+            assert_optimized_matches_unoptimized(source, Config::default());
+        }
+    }
+
+    #[test]
+    fn optimized_matches_unoptimized_under_saturating() {
+        let config = Config {
+            cell_overflow: CellOverflow::Saturating,
+            ..Config::default()
+        };
+        for source in PROGRAMS {
+            assert_optimized_matches_unoptimized(source, config);
+        }
+    }
+
+    #[test]
+    fn erroring_multi_step_decrement_clear_still_errors() {
+        // [---] is not recognized as a clear idiom (only [-] is), so it must
+        // still surface the underflow that stepping through it hits.
+        let config = Config {
+            cell_overflow: CellOverflow::Erroring,
+            ..Config::default()
+        };
+        let result = run_source("++[---].", config, true);
+        assert!(matches!(result, Err(BfError::CellOverflow(_))));
+    }
+
+    #[test]
+    fn erroring_increment_past_max_errors() {
+        let config = Config {
+            cell_overflow: CellOverflow::Erroring,
+            ..Config::default()
+        };
+        let source = "+".repeat(256);
+        let result = run_source(&source, config, true);
+        assert!(matches!(result, Err(BfError::CellOverflow(_))));
+    }
+
+    #[test]
+    fn bounded_tape_errors_on_coalesced_move_that_transiently_leaves_bounds() {
+        let config = Config {
+            tape_size: 10,
+            ..Config::default()
+        };
+        // Nets back to the start, but a naive final-position-only check would
+        // miss that ">>>>>>>>>>><<<" briefly pushes the pointer to index 11.
+        let result = run_source(">>>>>>>>>>><<<", config, true);
+        assert!(matches!(result, Err(BfError::PointerOutOfBounds(_))));
+    }
+
+    #[test]
+    fn wrapping_tape_allows_the_same_excursion() {
+        let config = Config {
+            tape_size: 10,
+            tape_bounds: TapeBounds::Wrapping,
+            ..Config::default()
+        };
+        assert!(run_source(">>>>>>>>>>><<<", config, true).is_ok());
+    }
+
+    #[test]
+    fn bounded_tape_errors_on_mul_add_loop_that_transiently_leaves_bounds() {
+        // The MulAdd's net offset (2) stays on a 6-cell tape starting at index
+        // 5, but its body's first Move overshoots to index 9 before settling
+        // back, so the unoptimized run errors and the optimized run must too.
+        let config = Config {
+            tape_size: 6,
+            ..Config::default()
+        };
+        let unoptimized = run_source(">>>++[->>>><<+<<]", config, false);
+        let optimized = run_source(">>>++[->>>><<+<<]", config, true);
+        assert!(matches!(unoptimized, Err(BfError::PointerOutOfBounds(_))));
+        assert!(matches!(optimized, Err(BfError::PointerOutOfBounds(_))));
+    }
+
+    #[test]
+    fn optimized_matches_unoptimized_for_mul_add_factor_past_256() {
+        // A copy loop with 300 '+'s in its body builds a factor that
+        // truncates to 44 if cast to u8 before reaching Saturating
+        // arithmetic (0 + 44 = 44, not the 255 that 0 + 300 saturates to);
+        // kept at full precision, the optimized run must still match.
+        let source = format!("+[->{}<]", "+".repeat(300));
+        let config = Config {
+            cell_overflow: CellOverflow::Saturating,
+            ..Config::default()
+        };
+        assert_optimized_matches_unoptimized(&source, config);
+    }
+
+    #[test]
+    fn mul_add_on_an_already_zero_cell_is_a_no_op_even_if_the_tape_is_too_small_for_the_excursion() {
+        // The loop body (and its pointer excursion) never runs when the cell
+        // starts at 0, so this must succeed despite a swept range that would
+        // fall off a 3-cell tape if the body ran.
+        let config = Config {
+            tape_size: 3,
+            ..Config::default()
+        };
+        assert!(run_source("[->>>><<+<<]", config, true).is_ok());
+    }
+
+    #[test]
+    fn erroring_mul_add_factor_past_256_still_errors() {
+        // Same idiom under Erroring: a truncated factor of 44 would add
+        // cleanly where the untruncated 300 overflows, so the optimized run
+        // must still error like the unoptimized one does.
+        let source = format!("+[->{}<]", "+".repeat(300));
+        let config = Config {
+            cell_overflow: CellOverflow::Erroring,
+            ..Config::default()
+        };
+        let unoptimized = run_source(&source, config, false);
+        let optimized = run_source(&source, config, true);
+        assert!(matches!(unoptimized, Err(BfError::CellOverflow(_))));
+        assert!(matches!(optimized, Err(BfError::CellOverflow(_))));
+    }
+}
@@ -0,0 +1,46 @@
+//! Human-readable error reporting for spans of source text.
+
+use std::fmt;
+use std::ops::Range;
+
+/// A pointed error message anchored to a byte-offset span in the source.
+#[derive(Debug)]
+pub struct Diagnostic {
+    message: String,
+    line_number: usize,
+    column: usize,
+    line: String,
+    caret_offset: usize,
+}
+
+impl Diagnostic {
+    /// Build a diagnostic for `span` within `source`, carrying `message`.
+    pub fn new(source: &str, span: Range<usize>, message: impl Into<String>) -> Self {
+        let line_start = source[..span.start].rfind('\n').map_or(0, |i| i + 1);
+        let line_end = source[span.start..]
+            .find('\n')
+            .map_or(source.len(), |i| span.start + i);
+        let line_number = source[..span.start].matches('\n').count() + 1;
+        Self {
+            message: message.into(),
+            line_number,
+            column: span.start - line_start + 1,
+            line: source[line_start..line_end].to_string(),
+            caret_offset: span.start - line_start,
+        }
+    }
+}
+
+impl fmt::Display for Diagnostic {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(
+            f,
+            "error at line {}, column {}: {}",
+            self.line_number, self.column, self.message
+        )?;
+        writeln!(f, "{}", self.line)?;
+        write!(f, "{}^", " ".repeat(self.caret_offset))
+    }
+}
+
+impl std::error::Error for Diagnostic {}
@@ -0,0 +1,14 @@
+use crate::Diagnostic;
+
+/// The typed error surface shared by parsing and interpretation.
+#[derive(Debug, thiserror::Error)]
+pub enum BfError {
+    #[error(transparent)]
+    UnmatchedBracket(#[from] Diagnostic),
+    #[error("pointer out of bounds at index {0}")]
+    PointerOutOfBounds(usize),
+    #[error("cell overflow at pointer {0}")]
+    CellOverflow(usize),
+    #[error(transparent)]
+    Io(#[from] std::io::Error),
+}
@@ -0,0 +1,148 @@
+//! Lexer and parser for Brainfuck source, producing an `Ast` of `Node`s.
+
+use std::ops::Range;
+
+mod diagnostic;
+mod error;
+mod optimize;
+
+pub use diagnostic::Diagnostic;
+pub use error::BfError;
+pub use optimize::optimize;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Token {
+    OpenLoop,
+    CloseLoop,
+    Expr(char),
+    Comment,
+}
+
+impl From<char> for Token {
+    fn from(value: char) -> Self {
+        match value {
+            '>' | '<' | '.' | ',' | '+' | '-' => Self::Expr(value),
+            '[' => Self::OpenLoop,
+            ']' => Self::CloseLoop,
+            _ => Self::Comment,
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub enum Node {
+    Incr,
+    Decr,
+    ShiftLeft,
+    ShiftRight,
+    Output,
+    Input,
+    Loop { children: Vec<Node> },
+    /// A run of consecutive `Incr`/`Decr` collapsed into one signed delta.
+    /// The count is kept at full precision (not wrapped to a byte) so that
+    /// `CellOverflow::Saturating`/`Erroring` still see the true magnitude of
+    /// the run, even though a single cell only ever holds a `u8`.
+    Add(i64),
+    /// A run of consecutive `ShiftLeft`/`ShiftRight` collapsed into one net
+    /// offset. `swept` is the inclusive range of offsets (relative to the
+    /// pointer's position before the run) the pointer passed through, so a
+    /// bounds check can still catch excursions that the net `offset` alone
+    /// would hide.
+    Move { offset: isize, swept: (isize, isize) },
+    /// The idiom `[-]`: zero the current cell in one step.
+    SetZero,
+    /// The copy/multiply idiom `[- >+ <]`: add `factor` times the current
+    /// cell's value to the cell at `offset` and zero the current cell.
+    /// `factor` is kept at full precision for the same reason as `Add`.
+    /// `swept` is the inclusive range of offsets (relative to the pointer
+    /// before the loop runs) that the loop body's two `Move`s pass through,
+    /// so a transient excursion out of bounds is still caught even though
+    /// the net movement is zero.
+    MulAdd {
+        offset: isize,
+        factor: i64,
+        swept: (isize, isize),
+    },
+}
+
+pub type Ast = Vec<Node>;
+pub type Tokens = Vec<(Token, Range<usize>)>;
+
+pub fn tokenize(input: &str) -> Tokens {
+    input
+        .char_indices()
+        .map(|(i, c)| (c.into(), i..i + c.len_utf8()))
+        .collect()
+}
+
+fn build_ast<I>(
+    tokens: &mut I,
+    source: &str,
+    open_spans: &mut Vec<Range<usize>>,
+) -> Result<Ast, BfError>
+where
+    I: Iterator<Item = (Token, Range<usize>)>,
+{
+    let mut ast = vec![];
+    while let Some((token, span)) = tokens.next() {
+        match token {
+            Token::Expr(c) => match c {
+                '>' => ast.push(Node::ShiftRight),
+                '<' => ast.push(Node::ShiftLeft),
+                '+' => ast.push(Node::Incr),
+                '-' => ast.push(Node::Decr),
+                '.' => ast.push(Node::Output),
+                ',' => ast.push(Node::Input),
+                _ => unreachable!("Token::Expr is only constructed for '>' '<' '+' '-' '.' ','"),
+            },
+            Token::OpenLoop => {
+                open_spans.push(span);
+                let children = build_ast(tokens, source, open_spans)?;
+                open_spans.pop();
+                ast.push(Node::Loop { children });
+            }
+            Token::CloseLoop => {
+                if open_spans.is_empty() {
+                    let diagnostic = Diagnostic::new(source, span, "unmatched ']' has no opening '['");
+                    return Err(BfError::UnmatchedBracket(diagnostic));
+                }
+                return Ok(ast);
+            }
+            Token::Comment => {}
+        }
+    }
+    if let Some(unclosed) = open_spans.last() {
+        let diagnostic = Diagnostic::new(source, unclosed.clone(), "unmatched '[' opened here");
+        return Err(BfError::UnmatchedBracket(diagnostic));
+    }
+    Ok(ast)
+}
+
+pub fn parse(input: &str) -> Result<Ast, BfError> {
+    build_ast(&mut tokenize(input).into_iter(), input, &mut vec![])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_simple_loop() {
+        let ast = parse("+[-]").unwrap();
+        assert!(matches!(ast.as_slice(), [Node::Incr, Node::Loop { .. }]));
+    }
+
+    #[test]
+    fn errors_on_unclosed_loop() {
+        let err = parse("++[++").unwrap_err();
+        assert!(matches!(err, BfError::UnmatchedBracket(_)));
+        assert!(err.to_string().contains("unmatched '[' opened here"));
+    }
+
+    #[test]
+    fn errors_on_stray_closing_bracket() {
+        let err = parse("++]").unwrap_err();
+        assert!(matches!(err, BfError::UnmatchedBracket(_)));
+        assert!(err.to_string().contains("unmatched ']' has no opening '['"));
+    }
+}
@@ -0,0 +1,174 @@
+//! An optimization pass that rewrites an `Ast` into a denser IR before
+//! `_interpret` (or the JIT) runs it, cutting instruction dispatch on
+//! loop-heavy programs while preserving observable I/O order.
+
+use crate::{Ast, Node};
+
+/// Optimize `ast`: coalesce runs of `Incr`/`Decr` and `ShiftLeft`/`ShiftRight`,
+/// and recognize the `[-]` clear idiom and the `[- >+ <]` copy/multiply
+/// idiom, at every nesting depth.
+pub fn optimize(ast: Ast) -> Ast {
+    recognize(coalesce(ast))
+}
+
+/// Recursively replace each `Loop` with its recognized form, innermost
+/// loops first, so a clear/multiply idiom nested inside an outer loop is
+/// matched just as readily as one at the top level.
+fn recognize(ast: Ast) -> Ast {
+    ast.into_iter()
+        .map(|node| match node {
+            Node::Loop { children } => recognize_loop(recognize(children)),
+            other => other,
+        })
+        .collect()
+}
+
+/// Collapse consecutive `Incr`/`Decr` into `Add` and `ShiftLeft`/`ShiftRight`
+/// into `Move`, recursing into loop bodies.
+fn coalesce(ast: Ast) -> Ast {
+    let mut out = Vec::with_capacity(ast.len());
+    for node in ast {
+        match node {
+            Node::Incr => bump_add(&mut out, 1),
+            Node::Decr => bump_add(&mut out, -1),
+            Node::ShiftLeft => bump_move(&mut out, -1),
+            Node::ShiftRight => bump_move(&mut out, 1),
+            Node::Loop { children } => out.push(Node::Loop {
+                children: coalesce(children),
+            }),
+            other => out.push(other),
+        }
+    }
+    out
+}
+
+fn bump_add(out: &mut Ast, delta: i64) {
+    if let Some(Node::Add(total)) = out.last_mut() {
+        *total += delta;
+    } else {
+        out.push(Node::Add(delta));
+    }
+}
+
+fn bump_move(out: &mut Ast, delta: isize) {
+    if let Some(Node::Move { offset, swept }) = out.last_mut() {
+        *offset += delta;
+        swept.0 = swept.0.min(*offset);
+        swept.1 = swept.1.max(*offset);
+    } else {
+        out.push(Node::Move {
+            offset: delta,
+            swept: (delta.min(0), delta.max(0)),
+        });
+    }
+}
+
+/// Recognize the `[-]` clear idiom and the `[- >+ <]` copy/multiply idiom
+/// in an already-recognized loop's children; otherwise keep it as a plain
+/// `Loop`.
+///
+/// Only `[-]` itself (a single `Add(-1)`) is recognized as a clear, not
+/// `[--]`/`[-5]`/etc.: stepping down by exactly 1 each iteration reaches 0
+/// and stops without ever under/overflowing, so it is safe to rewrite
+/// under wrapping, saturating, and erroring cell arithmetic alike. A
+/// larger decrement can underflow mid-loop under `CellOverflow::Erroring`
+/// (and `[+]`/larger increments only reach 0 by wrapping past 255), so
+/// those are left as plain loops.
+fn recognize_loop(children: Ast) -> Node {
+    if let [Node::Add(-1)] = children.as_slice() {
+        return Node::SetZero;
+    }
+    if let [Node::Add(-1), Node::Move { offset, swept: swept_there }, Node::Add(factor), Node::Move { offset: back, swept: swept_back }] =
+        children.as_slice()
+    {
+        if *back == -offset && *factor > 0 {
+            // `swept_back` is relative to the pointer's position after the
+            // first move (i.e. `offset`); shift it back to be relative to
+            // the pointer at loop entry, like `swept_there` already is.
+            let swept = (
+                swept_there.0.min(offset + swept_back.0),
+                swept_there.1.max(offset + swept_back.1),
+            );
+            return Node::MulAdd {
+                offset: *offset,
+                factor: *factor,
+                swept,
+            };
+        }
+    }
+    Node::Loop { children }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parse;
+
+    fn optimize_source(source: &str) -> Ast {
+        optimize(parse(source).unwrap())
+    }
+
+    #[test]
+    fn coalesces_runs_into_add_and_move() {
+        let ast = optimize_source("+++>><");
+        assert!(matches!(ast.as_slice(), [Node::Add(3), Node::Move { offset: 1, .. }]));
+    }
+
+    #[test]
+    fn recognizes_top_level_clear_loop() {
+        let ast = optimize_source("[-]");
+        assert!(matches!(ast.as_slice(), [Node::SetZero]));
+    }
+
+    #[test]
+    fn recognizes_clear_loop_nested_inside_another_loop() {
+        // The outer loop is not itself a recognizable idiom; only its body is.
+        let ast = optimize_source("+++[>+++[-]<-]");
+        let Node::Loop { children } = &ast[1] else {
+            panic!("expected the outer loop to remain a Loop, got {ast:?}");
+        };
+        assert!(
+            children.iter().any(|node| matches!(node, Node::SetZero)),
+            "expected a nested SetZero, got {children:?}"
+        );
+    }
+
+    #[test]
+    fn does_not_recognize_multi_step_decrement_as_clear() {
+        // [---] only reaches zero by stepping through an intermediate
+        // underflow under CellOverflow::Erroring, so it must stay a Loop.
+        let ast = optimize_source("[---]");
+        assert!(matches!(ast.as_slice(), [Node::Loop { .. }]));
+    }
+
+    #[test]
+    fn does_not_recognize_incrementing_clear() {
+        let ast = optimize_source("[+]");
+        assert!(matches!(ast.as_slice(), [Node::Loop { .. }]));
+    }
+
+    #[test]
+    fn recognizes_copy_multiply_loop() {
+        let ast = optimize_source("[->+++<]");
+        assert!(matches!(
+            ast.as_slice(),
+            [Node::MulAdd {
+                offset: 1,
+                factor: 3,
+                swept: (0, 1)
+            }]
+        ));
+    }
+
+    #[test]
+    fn mul_add_swept_covers_the_full_excursion_past_the_net_offset() {
+        // The first move overshoots to offset 4 before settling at the net
+        // offset of 2, so swept must reach 4, not just 2.
+        let ast = optimize_source("[->>>><<+<<]");
+        let Node::MulAdd { offset, swept, .. } = ast[0] else {
+            panic!("expected a MulAdd, got {ast:?}");
+        };
+        assert_eq!(offset, 2);
+        assert_eq!(swept, (0, 4));
+    }
+}
@@ -0,0 +1,220 @@
+//! Native-code execution path: compiles an `Ast` to machine code with Cranelift
+//! instead of walking it in `_interpret`.
+
+use std::io::{Read, Write};
+use std::mem;
+
+use cranelift_codegen::ir::{types, AbiParam, InstBuilder, MemFlags, UserFuncName};
+use cranelift_codegen::settings::{self, Configurable};
+use cranelift_frontend::{FunctionBuilder, FunctionBuilderContext, Variable};
+use cranelift_jit::{JITBuilder, JITModule};
+use cranelift_module::{default_libcall_names, Linkage, Module};
+
+use bf_parser::{Ast, Node};
+
+/// A program compiled to native code, ready to run against a tape.
+pub struct CompiledProgram {
+    // Kept alive only so the module's memory mapping outlives `entry`; never read again.
+    _module: JITModule,
+    entry: *const u8,
+}
+
+impl CompiledProgram {
+    /// Run the compiled program against `data`, starting at `ptr`.
+    pub fn run(&self, data: &mut [u8; 30_000], ptr: usize) {
+        let entry = unsafe { mem::transmute::<*const u8, extern "C" fn(*mut u8, i64) -> i64>(self.entry) };
+        entry(data.as_mut_ptr(), ptr as i64);
+    }
+}
+
+extern "C" fn putchar_trampoline(value: u8) {
+    std::io::stdout().write_all(&[value]).unwrap();
+}
+
+extern "C" fn getchar_trampoline() -> u8 {
+    let mut byte = [0_u8];
+    std::io::stdin().read_exact(&mut byte).unwrap();
+    byte[0]
+}
+
+/// Lower `ast` into Cranelift IR and JIT-compile it to native code.
+pub fn compile(ast: &Ast) -> Result<CompiledProgram, Box<dyn std::error::Error>> {
+    let mut flag_builder = settings::builder();
+    flag_builder.set("use_colocated_libcalls", "false")?;
+    flag_builder.set("is_pic", "false")?;
+    let isa_builder = cranelift_native::builder().map_err(|msg| msg.to_string())?;
+    let isa = isa_builder.finish(settings::Flags::new(flag_builder))?;
+
+    let mut jit_builder = JITBuilder::with_isa(isa, default_libcall_names());
+    jit_builder.symbol("putchar", putchar_trampoline as *const u8);
+    jit_builder.symbol("getchar", getchar_trampoline as *const u8);
+    let mut module = JITModule::new(jit_builder);
+
+    let pointer_type = module.target_config().pointer_type();
+
+    let mut putchar_sig = module.make_signature();
+    putchar_sig.params.push(AbiParam::new(types::I8));
+    let putchar_func = module.declare_function("putchar", Linkage::Import, &putchar_sig)?;
+
+    let mut getchar_sig = module.make_signature();
+    getchar_sig.returns.push(AbiParam::new(types::I8));
+    let getchar_func = module.declare_function("getchar", Linkage::Import, &getchar_sig)?;
+
+    let mut sig = module.make_signature();
+    sig.params.push(AbiParam::new(pointer_type));
+    sig.params.push(AbiParam::new(types::I64));
+    sig.returns.push(AbiParam::new(types::I64));
+    let program_func = module.declare_function("bf_main", Linkage::Local, &sig)?;
+
+    let mut ctx = module.make_context();
+    ctx.func.signature = sig;
+    ctx.func.name = UserFuncName::user(0, program_func.as_u32());
+
+    {
+        let mut fn_builder_ctx = FunctionBuilderContext::new();
+        let mut builder = FunctionBuilder::new(&mut ctx.func, &mut fn_builder_ctx);
+
+        let putchar_ref = module.declare_func_in_func(putchar_func, builder.func);
+        let getchar_ref = module.declare_func_in_func(getchar_func, builder.func);
+
+        let entry = builder.create_block();
+        builder.append_block_params_for_function_params(entry);
+        builder.switch_to_block(entry);
+        builder.seal_block(entry);
+
+        let data = builder.block_params(entry)[0];
+        let ptr_var = Variable::from_u32(0);
+        builder.declare_var(ptr_var, types::I64);
+        let initial_ptr = builder.block_params(entry)[1];
+        builder.def_var(ptr_var, initial_ptr);
+
+        let mut emitter = Emitter {
+            builder: &mut builder,
+            data,
+            ptr_var,
+            putchar_ref,
+            getchar_ref,
+        };
+        emitter.emit(ast);
+
+        let final_ptr = emitter.builder.use_var(ptr_var);
+        emitter.builder.ins().return_(&[final_ptr]);
+        builder.finalize();
+    }
+
+    module.define_function(program_func, &mut ctx)?;
+    module.clear_context(&mut ctx);
+    module.finalize_definitions()?;
+
+    let entry = module.get_finalized_function(program_func);
+    Ok(CompiledProgram {
+        _module: module,
+        entry,
+    })
+}
+
+/// Walks `Node`s, emitting Cranelift IR into a single function body.
+struct Emitter<'a, 'b> {
+    builder: &'a mut FunctionBuilder<'b>,
+    data: cranelift_codegen::ir::Value,
+    ptr_var: Variable,
+    putchar_ref: cranelift_codegen::ir::FuncRef,
+    getchar_ref: cranelift_codegen::ir::FuncRef,
+}
+
+impl Emitter<'_, '_> {
+    fn cell_addr(&mut self) -> cranelift_codegen::ir::Value {
+        let ptr = self.builder.use_var(self.ptr_var);
+        self.builder.ins().iadd(self.data, ptr)
+    }
+
+    fn emit(&mut self, ast: &Ast) {
+        for node in ast {
+            match node {
+                Node::Incr => {
+                    let addr = self.cell_addr();
+                    let value = self.builder.ins().load(types::I8, MemFlags::new(), addr, 0);
+                    let incremented = self.builder.ins().iadd_imm(value, 1);
+                    self.builder.ins().store(MemFlags::new(), incremented, addr, 0);
+                }
+                Node::Decr => {
+                    let addr = self.cell_addr();
+                    let value = self.builder.ins().load(types::I8, MemFlags::new(), addr, 0);
+                    let decremented = self.builder.ins().iadd_imm(value, -1);
+                    self.builder.ins().store(MemFlags::new(), decremented, addr, 0);
+                }
+                Node::ShiftLeft => {
+                    let ptr = self.builder.use_var(self.ptr_var);
+                    let moved = self.builder.ins().iadd_imm(ptr, -1);
+                    self.builder.def_var(self.ptr_var, moved);
+                }
+                Node::ShiftRight => {
+                    let ptr = self.builder.use_var(self.ptr_var);
+                    let moved = self.builder.ins().iadd_imm(ptr, 1);
+                    self.builder.def_var(self.ptr_var, moved);
+                }
+                Node::Add(delta) => {
+                    let addr = self.cell_addr();
+                    let value = self.builder.ins().load(types::I8, MemFlags::new(), addr, 0);
+                    let added = self.builder.ins().iadd_imm(value, *delta);
+                    self.builder.ins().store(MemFlags::new(), added, addr, 0);
+                }
+                Node::Move { offset, .. } => {
+                    let ptr = self.builder.use_var(self.ptr_var);
+                    let moved = self.builder.ins().iadd_imm(ptr, *offset as i64);
+                    self.builder.def_var(self.ptr_var, moved);
+                }
+                Node::SetZero => {
+                    let addr = self.cell_addr();
+                    let zero = self.builder.ins().iconst(types::I8, 0);
+                    self.builder.ins().store(MemFlags::new(), zero, addr, 0);
+                }
+                Node::MulAdd { offset, factor, .. } => {
+                    let addr = self.cell_addr();
+                    let value = self.builder.ins().load(types::I8, MemFlags::new(), addr, 0);
+                    let scaled = self.builder.ins().imul_imm(value, *factor);
+                    let ptr = self.builder.use_var(self.ptr_var);
+                    let target_ptr = self.builder.ins().iadd_imm(ptr, *offset as i64);
+                    let target_addr = self.builder.ins().iadd(self.data, target_ptr);
+                    let target_value = self.builder.ins().load(types::I8, MemFlags::new(), target_addr, 0);
+                    let added = self.builder.ins().iadd(target_value, scaled);
+                    self.builder.ins().store(MemFlags::new(), added, target_addr, 0);
+                    let zero = self.builder.ins().iconst(types::I8, 0);
+                    self.builder.ins().store(MemFlags::new(), zero, addr, 0);
+                }
+                Node::Output => {
+                    let addr = self.cell_addr();
+                    let value = self.builder.ins().load(types::I8, MemFlags::new(), addr, 0);
+                    self.builder.ins().call(self.putchar_ref, &[value]);
+                }
+                Node::Input => {
+                    let call = self.builder.ins().call(self.getchar_ref, &[]);
+                    let value = self.builder.inst_results(call)[0];
+                    let addr = self.cell_addr();
+                    self.builder.ins().store(MemFlags::new(), value, addr, 0);
+                }
+                Node::Loop { children } => {
+                    let header = self.builder.create_block();
+                    let body = self.builder.create_block();
+                    let continuation = self.builder.create_block();
+
+                    self.builder.ins().jump(header, &[]);
+
+                    self.builder.switch_to_block(header);
+                    let addr = self.cell_addr();
+                    let value = self.builder.ins().load(types::I8, MemFlags::new(), addr, 0);
+                    self.builder.ins().brif(value, body, &[], continuation, &[]);
+
+                    self.builder.switch_to_block(body);
+                    self.builder.seal_block(body);
+                    self.emit(children);
+                    self.builder.ins().jump(header, &[]);
+                    self.builder.seal_block(header);
+
+                    self.builder.switch_to_block(continuation);
+                    self.builder.seal_block(continuation);
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,64 @@
+//! Interactive REPL that keeps the tape and pointer alive across lines.
+
+use bf_interp::Interpreter;
+use bf_parser::{optimize, parse};
+use rustyline::error::ReadlineError;
+use rustyline::DefaultEditor;
+
+fn eval(interpreter: &mut Interpreter, line: &str) {
+    let ast = match parse(line).map(optimize) {
+        Ok(ast) => ast,
+        Err(err) => {
+            eprintln!("{err}");
+            return;
+        }
+    };
+    let mut input = std::io::stdin();
+    let mut output = std::io::stdout();
+    if let Err(err) = interpreter.run(&ast, &mut input, &mut output) {
+        eprintln!("{err}");
+    }
+}
+
+fn dump_tape(interpreter: &Interpreter) {
+    let ptr = interpreter.ptr();
+    let tape = interpreter.tape();
+    let start = ptr.saturating_sub(8);
+    let end = (ptr + 8).min(tape.len() - 1);
+    for (i, cell) in tape.iter().enumerate().take(end + 1).skip(start) {
+        if i == ptr {
+            print!("[{cell}] ");
+        } else {
+            print!("{cell} ");
+        }
+    }
+    println!();
+}
+
+/// Run the interactive REPL until the user exits with Ctrl-C/Ctrl-D.
+pub fn run() -> rustyline::Result<()> {
+    let mut interpreter = Interpreter::new();
+    let mut editor = DefaultEditor::new()?;
+    loop {
+        match editor.readline("bf> ") {
+            Ok(line) => {
+                let line = line.trim();
+                if line.is_empty() {
+                    continue;
+                }
+                editor.add_history_entry(line)?;
+                match line {
+                    ":reset" => interpreter.reset(),
+                    ":tape" => dump_tape(&interpreter),
+                    _ => eval(&mut interpreter, line),
+                }
+            }
+            Err(ReadlineError::Interrupted) | Err(ReadlineError::Eof) => break,
+            Err(err) => {
+                eprintln!("{err}");
+                break;
+            }
+        }
+    }
+    Ok(())
+}